@@ -1,5 +1,6 @@
 use pyo3::prelude::*;
 use flate2::{Compression, write::GzEncoder, read::GzDecoder};
+use std::collections::HashMap;
 use std::io::{Write, Read};
 
 /// Compress data using gzip
@@ -63,4 +64,181 @@ pub fn brotli_decompress(data: &[u8]) -> PyResult<Vec<u8>> {
     reader.read_to_end(&mut result)
         .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("brotli decompression error: {}", e)))?;
     Ok(result)
+}
+
+/// Parse an `Accept-Encoding` header into (encoding, q) pairs, treating a missing `q` as 1.0.
+fn parse_accept_encoding(header: &str) -> Vec<(String, f32)> {
+    header
+        .split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            if part.is_empty() {
+                return None;
+            }
+            let mut segments = part.split(';');
+            let name = segments.next()?.trim().to_ascii_lowercase();
+            let mut q = 1.0f32;
+            for segment in segments {
+                if let Some(value) = segment.trim().strip_prefix("q=") {
+                    q = value.parse().unwrap_or(1.0);
+                }
+            }
+            Some((name, q))
+        })
+        .collect()
+}
+
+/// Pick the best codec from `accept_encoding`, honoring `q=` weights and the
+/// `identity`/`*` tokens, within a configurable preference order.
+fn negotiate_encoding(accept_encoding: &str, preference: &[&str]) -> Option<&'static str> {
+    let offered = parse_accept_encoding(accept_encoding);
+
+    let weight_of = |name: &str| -> Option<f32> {
+        if let Some((_, q)) = offered.iter().find(|(n, _)| n == name) {
+            return (*q > 0.0).then_some(*q);
+        }
+        if let Some((_, q)) = offered.iter().find(|(n, _)| n == "*") {
+            return (*q > 0.0).then_some(*q);
+        }
+        None
+    };
+
+    // `max_by` keeps the *last* equally-weighted element, which would invert
+    // `preference`'s ordering on ties (the overwhelmingly common case with no `q=` or a
+    // bare `*`); fold by strict `>` instead so the earliest-preferred codec wins ties.
+    let mut best: Option<(&'static str, f32)> = None;
+    for codec in preference {
+        if let Some(q) = weight_of(codec) {
+            if best.map_or(true, |(_, best_q)| q > best_q) {
+                best = Some((*codec, q));
+            }
+        }
+    }
+
+    // An explicitly offered `identity` that outweighs every codec wins: no compression.
+    let identity_q = offered.iter().find(|(n, _)| n == "identity").map(|(_, q)| *q);
+    match (best, identity_q) {
+        (Some((_, best_q)), Some(id_q)) if id_q > best_q => None,
+        _ => best.map(|(codec, _)| codec),
+    }
+}
+
+/// Negotiate a `Content-Encoding` from an `Accept-Encoding` header and compress `data`
+/// with the winning codec (zstd > brotli > gzip by default). Returns `data` untouched under
+/// `identity` when nothing suitable is offered or `data` is smaller than `min_size`.
+#[pyfunction]
+#[pyo3(signature = (data, accept_encoding, min_size=256, quality_overrides=None))]
+pub fn negotiate_and_compress(
+    data: &[u8],
+    accept_encoding: &str,
+    min_size: usize,
+    quality_overrides: Option<HashMap<String, i32>>,
+) -> PyResult<(Vec<u8>, String)> {
+    if data.len() < min_size {
+        return Ok((data.to_vec(), "identity".to_string()));
+    }
+
+    let preference = ["zstd", "br", "gzip"];
+    let quality = |codec: &str| quality_overrides.as_ref().and_then(|q| q.get(codec)).copied();
+
+    match negotiate_encoding(accept_encoding, &preference) {
+        Some("zstd") => Ok((zstd_compress(data, quality("zstd"))?, "zstd".to_string())),
+        Some("br") => Ok((
+            brotli_compress(data, quality("br").map(|q| q as u32))?,
+            "br".to_string(),
+        )),
+        Some("gzip") => Ok((
+            gzip_compress(data, quality("gzip").map(|q| q as u32))?,
+            "gzip".to_string(),
+        )),
+        _ => Ok((data.to_vec(), "identity".to_string())),
+    }
+}
+
+/// Incrementally compresses a stream of chunks behind a `write`/`finish` interface, so a
+/// large response body can be compressed without buffering the whole payload in memory.
+#[pyclass]
+pub struct StreamingCompressor {
+    encoding: String,
+    gzip: Option<GzEncoder<Vec<u8>>>,
+    zstd: Option<zstd::stream::write::Encoder<'static, Vec<u8>>>,
+    brotli: Option<brotli::CompressorWriter<Vec<u8>>>,
+}
+
+#[pymethods]
+impl StreamingCompressor {
+    #[new]
+    #[pyo3(signature = (encoding, level=None))]
+    pub fn new(encoding: &str, level: Option<i32>) -> PyResult<Self> {
+        let mut compressor = Self {
+            encoding: encoding.to_string(),
+            gzip: None,
+            zstd: None,
+            brotli: None,
+        };
+        match encoding {
+            "gzip" => {
+                let compression_level = level.map(|l| Compression::new(l as u32)).unwrap_or_default();
+                compressor.gzip = Some(GzEncoder::new(Vec::new(), compression_level));
+            }
+            "zstd" => {
+                compressor.zstd = Some(
+                    zstd::stream::write::Encoder::new(Vec::new(), level.unwrap_or(3)).map_err(|e| {
+                        pyo3::exceptions::PyIOError::new_err(format!("zstd stream error: {}", e))
+                    })?,
+                );
+            }
+            "br" => {
+                compressor.brotli = Some(brotli::CompressorWriter::new(
+                    Vec::new(),
+                    4096,
+                    level.unwrap_or(5) as u32,
+                    22,
+                ));
+            }
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unsupported encoding: {}",
+                    other
+                )))
+            }
+        }
+        Ok(compressor)
+    }
+
+    /// Compress and buffer the next chunk of the body.
+    pub fn write(&mut self, chunk: &[u8]) -> PyResult<()> {
+        let result = if let Some(encoder) = self.gzip.as_mut() {
+            encoder.write_all(chunk)
+        } else if let Some(encoder) = self.zstd.as_mut() {
+            encoder.write_all(chunk)
+        } else if let Some(encoder) = self.brotli.as_mut() {
+            encoder.write_all(chunk)
+        } else {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err("finish() already called"));
+        };
+        result.map_err(|e| {
+            pyo3::exceptions::PyIOError::new_err(format!("{} compression error: {}", self.encoding, e))
+        })
+    }
+
+    /// Finish the stream and return the fully-compressed bytes.
+    pub fn finish(&mut self) -> PyResult<Vec<u8>> {
+        if let Some(encoder) = self.gzip.take() {
+            encoder
+                .finish()
+                .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("gzip compression error: {}", e)))
+        } else if let Some(encoder) = self.zstd.take() {
+            encoder
+                .finish()
+                .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("zstd compression error: {}", e)))
+        } else if let Some(mut encoder) = self.brotli.take() {
+            encoder
+                .flush()
+                .map_err(|e| pyo3::exceptions::PyIOError::new_err(format!("brotli compression error: {}", e)))?;
+            Ok(encoder.into_inner())
+        } else {
+            Err(pyo3::exceptions::PyRuntimeError::new_err("finish() already called"))
+        }
+    }
 }
\ No newline at end of file
@@ -4,10 +4,76 @@ use sha2::{Sha256, Sha512};
 use pbkdf2::pbkdf2;
 use base64::{engine::general_purpose, Engine as _};
 use rand::RngCore;
+use hkdf::Hkdf;
+use chacha20poly1305::{aead::Aead, aead::Payload, ChaCha20Poly1305, KeyInit, Nonce};
 
 type HmacSha256 = Hmac<Sha256>;
 type HmacSha512 = Hmac<Sha512>;
 
+/// HKDF info label binding derived cookie-encryption keys to their purpose, so they never
+/// collide with the signing key used by `sign_cookie`.
+const COOKIE_ENCRYPTION_INFO: &[u8] = b"haske-cookie-encryption-v1";
+/// Version byte prepended to every encrypted cookie token, so the format can evolve.
+const COOKIE_TOKEN_VERSION: u8 = 1;
+const COOKIE_NONCE_LEN: usize = 12;
+
+fn derive_cookie_key(key: &str) -> PyResult<[u8; 32]> {
+    let hk = Hkdf::<Sha256>::new(None, key.as_bytes());
+    let mut subkey = [0u8; 32];
+    hk.expand(COOKIE_ENCRYPTION_INFO, &mut subkey)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("key derivation failed: {}", e)))?;
+    Ok(subkey)
+}
+
+/// Authenticated-encrypt `data` into a versioned base64 cookie token
+/// (`version || nonce || ciphertext || tag`), using a ChaCha20-Poly1305 key derived from
+/// `key` via HKDF-SHA256 so the encryption key never collides with the signing key.
+#[pyfunction]
+#[pyo3(signature = (data, key, associated_data=None))]
+pub fn encrypt_cookie(data: &[u8], key: &str, associated_data: Option<&[u8]>) -> PyResult<String> {
+    let subkey = derive_cookie_key(key)?;
+    let cipher = ChaCha20Poly1305::new(subkey.as_slice().into());
+
+    let nonce_bytes = generate_random_bytes(COOKIE_NONCE_LEN)?;
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, Payload { msg: data, aad: associated_data.unwrap_or_default() })
+        .map_err(|_| pyo3::exceptions::PyRuntimeError::new_err("cookie encryption failed"))?;
+
+    let mut token = Vec::with_capacity(1 + COOKIE_NONCE_LEN + ciphertext.len());
+    token.push(COOKIE_TOKEN_VERSION);
+    token.extend_from_slice(&nonce_bytes);
+    token.extend_from_slice(&ciphertext);
+    Ok(general_purpose::URL_SAFE_NO_PAD.encode(token))
+}
+
+/// Decrypt a token produced by `encrypt_cookie`. Returns a generic authentication failure
+/// without revealing whether the version, nonce, or tag was what caused it.
+#[pyfunction]
+#[pyo3(signature = (token, key, associated_data=None))]
+pub fn decrypt_cookie(token: &str, key: &str, associated_data: Option<&[u8]>) -> PyResult<Vec<u8>> {
+    let auth_failure = || pyo3::exceptions::PyValueError::new_err("cookie authentication failed");
+
+    let raw = general_purpose::URL_SAFE_NO_PAD
+        .decode(token)
+        .map_err(|_| auth_failure())?;
+
+    if raw.len() < 1 + COOKIE_NONCE_LEN || raw[0] != COOKIE_TOKEN_VERSION {
+        return Err(auth_failure());
+    }
+
+    let nonce = Nonce::from_slice(&raw[1..1 + COOKIE_NONCE_LEN]);
+    let ciphertext = &raw[1 + COOKIE_NONCE_LEN..];
+
+    let subkey = derive_cookie_key(key)?;
+    let cipher = ChaCha20Poly1305::new(subkey.as_slice().into());
+
+    cipher
+        .decrypt(nonce, Payload { msg: ciphertext, aad: associated_data.unwrap_or_default() })
+        .map_err(|_| auth_failure())
+}
+
 #[pyfunction]
 pub fn sign_cookie(secret: &str, payload: &str) -> PyResult<String> {
     let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
@@ -46,6 +112,93 @@ pub fn verify_cookie(secret: &str, token: &str) -> PyResult<Option<String>> {
     }
 }
 
+fn hmac_sign(secret: &str, alg: &str, data: &[u8]) -> PyResult<Vec<u8>> {
+    match alg {
+        "HS256" => {
+            let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{}", e)))?;
+            mac.update(data);
+            Ok(mac.finalize().into_bytes().to_vec())
+        }
+        "HS512" => {
+            let mut mac = HmacSha512::new_from_slice(secret.as_bytes())
+                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{}", e)))?;
+            mac.update(data);
+            Ok(mac.finalize().into_bytes().to_vec())
+        }
+        other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "unsupported JWT algorithm: {}",
+            other
+        ))),
+    }
+}
+
+fn jwt_header(alg: &str) -> PyResult<&'static str> {
+    match alg {
+        "HS256" => Ok("{\"alg\":\"HS256\",\"typ\":\"JWT\"}"),
+        "HS512" => Ok("{\"alg\":\"HS512\",\"typ\":\"JWT\"}"),
+        other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "unsupported JWT algorithm: {}",
+            other
+        ))),
+    }
+}
+
+/// Encode a standard three-part JWT (`header.payload.sig`, all base64url) over `claims_json`,
+/// signed with `alg` (`HS256` or `HS512`).
+#[pyfunction]
+pub fn jwt_encode(secret: &str, claims_json: &str, alg: &str) -> PyResult<String> {
+    let header_b64 = general_purpose::URL_SAFE_NO_PAD.encode(jwt_header(alg)?);
+    let payload_b64 = general_purpose::URL_SAFE_NO_PAD.encode(claims_json);
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+    let sig = hmac_sign(secret, alg, signing_input.as_bytes())?;
+    let sig_b64 = general_purpose::URL_SAFE_NO_PAD.encode(sig);
+    Ok(format!("{}.{}", signing_input, sig_b64))
+}
+
+/// Verify a JWT produced by `jwt_encode` and return its claims JSON. Verifies the
+/// signature in constant time and enforces the `exp`/`nbf` registered claims against `now`
+/// (seconds since the Unix epoch).
+#[pyfunction]
+pub fn jwt_decode(secret: &str, token: &str, alg: &str, now: i64) -> PyResult<String> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return Err(pyo3::exceptions::PyValueError::new_err("malformed JWT"));
+    }
+
+    let signing_input = format!("{}.{}", parts[0], parts[1]);
+    let expected_sig = hmac_sign(secret, alg, signing_input.as_bytes())?;
+    let actual_sig = general_purpose::URL_SAFE_NO_PAD
+        .decode(parts[2])
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err("invalid JWT signature encoding"))?;
+
+    if !constant_time_eq::constant_time_eq(&expected_sig, &actual_sig) {
+        return Err(pyo3::exceptions::PyValueError::new_err("invalid JWT signature"));
+    }
+
+    let payload_bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(parts[1])
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err("invalid JWT payload encoding"))?;
+    let payload_str = String::from_utf8(payload_bytes)
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err("JWT payload is not valid UTF-8"))?;
+
+    let claims: serde_json::Value = serde_json::from_str(&payload_str)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("invalid claims JSON: {}", e)))?;
+
+    if let Some(exp) = claims.get("exp").and_then(|v| v.as_i64()) {
+        if now >= exp {
+            return Err(pyo3::exceptions::PyValueError::new_err("token has expired"));
+        }
+    }
+    if let Some(nbf) = claims.get("nbf").and_then(|v| v.as_i64()) {
+        if now < nbf {
+            return Err(pyo3::exceptions::PyValueError::new_err("token is not yet valid"));
+        }
+    }
+
+    Ok(payload_str)
+}
+
 /// Hash password using PBKDF2 with SHA256
 #[pyfunction]
 pub fn hash_password(password: &str, salt: Option<Vec<u8>>) -> PyResult<(Vec<u8>, Vec<u8>)> {
@@ -14,7 +14,12 @@ mod compress;
 mod ws;
 
 use router::HaskeApp;
-use ws::{WebSocketFrame, WebSocketManager, WebSocketReceiver};
+use orm::{Transaction, TxnConflictError};
+use compress::StreamingCompressor;
+use ws::{
+    CloseCode, FrameDecoder, MessageAssembler, PerMessageDeflate, WebSocketFrame,
+    WebSocketManager, WebSocketReceiver, WsError,
+};
 
 // PyO3 module initializer for `_haske_core`.
 #[pymodule]
@@ -22,11 +27,17 @@ fn _haske_core(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     // Register classes
     m.add_class::<HaskeApp>()?;
     m.add_class::<cache::HaskeCache>()?;
+    m.add_class::<Transaction>()?;
+    m.add_class::<StreamingCompressor>()?;
     
     // WebSocket classes
     m.add_class::<WebSocketFrame>()?;
     m.add_class::<WebSocketManager>()?;
     m.add_class::<WebSocketReceiver>()?;
+    m.add_class::<MessageAssembler>()?;
+    m.add_class::<PerMessageDeflate>()?;
+    m.add_class::<CloseCode>()?;
+    m.add_class::<FrameDecoder>()?;
 
     // Register path functions
     m.add_function(wrap_pyfunction!(path::compile_path, m)?)?;
@@ -48,6 +59,10 @@ fn _haske_core(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(crypto::hash_password, m)?)?;
     m.add_function(wrap_pyfunction!(crypto::verify_password, m)?)?;
     m.add_function(wrap_pyfunction!(crypto::generate_random_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::jwt_encode, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::jwt_decode, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::encrypt_cookie, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::decrypt_cookie, m)?)?;
 
     // ORM helpers
     m.add_function(wrap_pyfunction!(orm::prepare_query, m)?)?;
@@ -58,12 +73,20 @@ fn _haske_core(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(orm::return_connection_to_pool, m)?)?;
     m.add_function(wrap_pyfunction!(orm::batch_insert, m)?)?;
     m.add_function(wrap_pyfunction!(orm::optimize_type_conversion, m)?)?;
+    m.add_function(wrap_pyfunction!(orm::convert_result_set, m)?)?;
     m.add_function(wrap_pyfunction!(orm::build_update_query, m)?)?;
     m.add_function(wrap_pyfunction!(orm::build_delete_query, m)?)?;
     m.add_function(wrap_pyfunction!(orm::validate_query_syntax, m)?)?;
     m.add_function(wrap_pyfunction!(orm::cache_prepared_statement, m)?)?;
     m.add_function(wrap_pyfunction!(orm::get_cached_statement, m)?)?;
     m.add_function(wrap_pyfunction!(orm::clear_statement_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(orm::begin_transaction, m)?)?;
+    m.add_function(wrap_pyfunction!(orm::set_savepoint, m)?)?;
+    m.add_function(wrap_pyfunction!(orm::rollback_to_savepoint, m)?)?;
+    m.add_function(wrap_pyfunction!(orm::release_savepoint, m)?)?;
+    m.add_function(wrap_pyfunction!(orm::commit, m)?)?;
+    m.add_function(wrap_pyfunction!(orm::rollback, m)?)?;
+    m.add("TxnConflictError", m.py().get_type_bound::<TxnConflictError>())?;
 
     // Cache helpers
     m.add_function(wrap_pyfunction!(cache::create_cache, m)?)?;
@@ -75,6 +98,7 @@ fn _haske_core(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(compress::zstd_decompress, m)?)?;
     m.add_function(wrap_pyfunction!(compress::brotli_compress, m)?)?;
     m.add_function(wrap_pyfunction!(compress::brotli_decompress, m)?)?;
+    m.add_function(wrap_pyfunction!(compress::negotiate_and_compress, m)?)?;
 
     // WebSocket helpers
     m.add_function(wrap_pyfunction!(ws::websocket_accept_key, m)?)?;
@@ -83,6 +107,8 @@ fn _haske_core(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(ws::is_final_frame, m)?)?;
     m.add_function(wrap_pyfunction!(ws::is_masked_frame, m)?)?;
     m.add_function(wrap_pyfunction!(ws::get_payload_length, m)?)?;
+    m.add_function(wrap_pyfunction!(ws::validate_server_frame, m)?)?;
+    m.add("WsError", m.py().get_type_bound::<WsError>())?;
 
     // Module metadata
     m.add("__doc__", "Haske core extension: fast routing, json, templates, crypto, orm, websocket helpers.")?;
@@ -1,8 +1,9 @@
 use pyo3::prelude::*;
-use pyo3::types::{PyAny, PyBool, PyDict, PyFloat, PyInt, PyString};
+use pyo3::types::{PyAny, PyBool, PyDict, PyFloat, PyInt, PyModule, PyString};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy;
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 
 
 /// Prepare a single SQL query with named parameters
@@ -241,7 +242,176 @@ pub fn optimize_type_conversion(
     Ok(optimized)
 }
 
-/// UPDATE query builder 
+/// A declarative per-column conversion applied by `convert_result_set`, in place of
+/// `optimize_type_conversion`'s type guessing.
+#[derive(Clone, Debug)]
+enum Conversion {
+    /// Leave the value as-is (covers the "string" short name).
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    /// RFC3339 timestamp, returned as a naive Python `datetime`.
+    Timestamp,
+    /// Timestamp parsed with a strftime-style format, returned as a naive `datetime`.
+    TimestampFmt(String),
+    /// Timestamp parsed with a strftime-style format that includes an offset, returned
+    /// as a timezone-aware `datetime`.
+    TimestampTzFmt(String),
+}
+
+impl Conversion {
+    /// Parse a conversion spec of the form `"name"` or `"name:format"`, where `format`
+    /// is a strftime-style format string for the timestamp variants. A `"tz:"` prefix on
+    /// the format selects the timezone-aware variant.
+    fn parse(spec: &str) -> PyResult<Self> {
+        let mut parts = spec.splitn(2, ':');
+        let name = parts.next().unwrap_or("");
+        let format = parts.next();
+
+        match name {
+            "bytes" | "string" => Ok(Conversion::Bytes),
+            "int" => Ok(Conversion::Integer),
+            "float" => Ok(Conversion::Float),
+            "bool" => Ok(Conversion::Boolean),
+            "timestamp" => match format {
+                Some(fmt) => match fmt.strip_prefix("tz:") {
+                    Some(tz_fmt) => Ok(Conversion::TimestampTzFmt(tz_fmt.to_string())),
+                    None => Ok(Conversion::TimestampFmt(fmt.to_string())),
+                },
+                None => Ok(Conversion::Timestamp),
+            },
+            other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown conversion: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Build a naive (tzinfo-less) Python `datetime` — used by `Timestamp`/`TimestampFmt`,
+/// which are documented not to attach an offset.
+fn naive_timestamp_to_py(py: Python<'_>, naive: NaiveDateTime) -> PyResult<Py<PyAny>> {
+    let datetime_cls = PyModule::import(py, "datetime")?.getattr("datetime")?;
+    let iso = naive.format("%Y-%m-%dT%H:%M:%S%.f").to_string();
+    let parsed = datetime_cls.call_method1("fromisoformat", (iso,))?;
+    Ok(parsed.unbind())
+}
+
+/// Build a timezone-aware Python `datetime` with the offset attached — used by
+/// `TimestampTzFmt`.
+fn aware_timestamp_to_py(py: Python<'_>, dt: DateTime<FixedOffset>) -> PyResult<Py<PyAny>> {
+    let datetime_cls = PyModule::import(py, "datetime")?.getattr("datetime")?;
+    let parsed = datetime_cls.call_method1("fromisoformat", (dt.to_rfc3339(),))?;
+    Ok(parsed.unbind())
+}
+
+fn apply_conversion(py: Python<'_>, conversion: &Conversion, value: Py<PyAny>) -> PyResult<Py<PyAny>> {
+    let bound = value.bind(py);
+    if bound.is_none() {
+        return Ok(value);
+    }
+
+    match conversion {
+        Conversion::Bytes => Ok(value),
+        Conversion::Integer => {
+            let s: String = bound.extract()?;
+            let i: i64 = s.trim().parse().map_err(|_| {
+                pyo3::exceptions::PyValueError::new_err(format!("cannot convert {:?} to int", s))
+            })?;
+            Ok(PyInt::new(py, i).unbind().into())
+        }
+        Conversion::Float => {
+            let s: String = bound.extract()?;
+            let f: f64 = s.trim().parse().map_err(|_| {
+                pyo3::exceptions::PyValueError::new_err(format!("cannot convert {:?} to float", s))
+            })?;
+            Ok(PyFloat::new(py, f).unbind().into())
+        }
+        Conversion::Boolean => {
+            let s: String = bound.extract()?;
+            let b = match s.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "cannot convert {:?} to bool",
+                        s
+                    )))
+                }
+            };
+            Ok(PyBool::new(py, b).to_owned().unbind().into())
+        }
+        Conversion::Timestamp => {
+            let s: String = bound.extract()?;
+            let dt = DateTime::parse_from_rfc3339(&s).map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "invalid RFC3339 timestamp {:?}: {}",
+                    s, e
+                ))
+            })?;
+            naive_timestamp_to_py(py, dt.naive_utc())
+        }
+        Conversion::TimestampFmt(fmt) => {
+            let s: String = bound.extract()?;
+            let naive = NaiveDateTime::parse_from_str(&s, fmt).map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "invalid timestamp {:?} for format {:?}: {}",
+                    s, fmt, e
+                ))
+            })?;
+            naive_timestamp_to_py(py, naive)
+        }
+        Conversion::TimestampTzFmt(fmt) => {
+            let s: String = bound.extract()?;
+            let dt = DateTime::parse_from_str(&s, fmt).map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "invalid timestamp {:?} for format {:?}: {}",
+                    s, fmt, e
+                ))
+            })?;
+            aware_timestamp_to_py(py, dt)
+        }
+    }
+}
+
+/// Apply one declarative `Conversion` per column to a result set, instead of guessing
+/// types value by value like `optimize_type_conversion`. `Integer`/`Float` parse
+/// strictly and raise on failure; `Boolean` accepts `true/false/1/0/yes/no`; the
+/// timestamp variants parse with chrono and return a Python `datetime`.
+#[pyfunction]
+pub fn convert_result_set(
+    py: Python<'_>,
+    rows: Vec<Vec<Py<PyAny>>>,
+    column_names: Vec<String>,
+    conversions: Vec<String>,
+) -> PyResult<Vec<Py<PyAny>>> {
+    if conversions.len() != column_names.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "conversions must have one entry per column",
+        ));
+    }
+    let parsed: Vec<Conversion> = conversions
+        .iter()
+        .map(|c| Conversion::parse(c))
+        .collect::<PyResult<_>>()?;
+
+    let mut processed = Vec::with_capacity(rows.len());
+    for row in rows {
+        let dict = PyDict::new(py);
+        for (i, value) in row.into_iter().enumerate() {
+            if i >= column_names.len() {
+                continue;
+            }
+            let converted = apply_conversion(py, &parsed[i], value)?;
+            dict.set_item(column_names[i].as_str(), converted)?;
+        }
+        processed.push(dict.into());
+    }
+    Ok(processed)
+}
+
+/// UPDATE query builder
 #[pyfunction]
 pub fn build_update_query(
     table: &str,
@@ -290,6 +460,115 @@ pub fn validate_query_syntax(sql: &str) -> PyResult<bool> {
     Ok(valid_starts.iter().any(|start| sql_upper.starts_with(start)))
 }
 
+/// Raised by `commit` when an optimistic transaction's deferred conflict check fails.
+/// Callers should treat this as retriable: re-run the transaction from the start.
+pyo3::create_exception!(_haske_core, TxnConflictError, pyo3::exceptions::PyException);
+
+/// A transaction checked out against a pooled connection, tracking an ordered stack of
+/// named savepoints. The connection it holds must not be returned to `CONNECTION_POOL`
+/// until `commit`/`rollback` has run.
+#[pyclass]
+pub struct Transaction {
+    #[pyo3(get)]
+    optimistic: bool,
+    conn: Py<PyAny>,
+    savepoints: Vec<String>,
+    #[pyo3(get)]
+    active: bool,
+}
+
+#[pymethods]
+impl Transaction {
+    /// The connection this transaction owns.
+    #[getter]
+    pub fn connection(&self, py: Python<'_>) -> Py<PyAny> {
+        self.conn.clone_ref(py)
+    }
+}
+
+fn ensure_active(txn: &Transaction) -> PyResult<()> {
+    if !txn.active {
+        return Err(pyo3::exceptions::PyRuntimeError::new_err(
+            "transaction is no longer active",
+        ));
+    }
+    Ok(())
+}
+
+/// Check out a transaction against `conn`. In optimistic mode, conflict detection is
+/// deferred to `commit`, mirroring optimistic-vs-pessimistic locking transaction options;
+/// in pessimistic mode, callers are expected to take row locks as they go.
+#[pyfunction]
+pub fn begin_transaction(conn: Py<PyAny>, optimistic: bool) -> Transaction {
+    Transaction {
+        optimistic,
+        conn,
+        savepoints: Vec::new(),
+        active: true,
+    }
+}
+
+/// Push a new named savepoint onto `txn`'s stack, returning the SQL to create it.
+#[pyfunction]
+pub fn set_savepoint(txn: &mut Transaction, name: String) -> PyResult<String> {
+    ensure_active(txn)?;
+    if txn.savepoints.contains(&name) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "savepoint {:?} already exists",
+            name
+        )));
+    }
+    txn.savepoints.push(name.clone());
+    Ok(format!("SAVEPOINT {}", name))
+}
+
+/// Roll back to a named savepoint, discarding any savepoints nested inside it (the named
+/// savepoint itself stays on the stack), and return the SQL to do so.
+#[pyfunction]
+pub fn rollback_to_savepoint(txn: &mut Transaction, name: &str) -> PyResult<String> {
+    ensure_active(txn)?;
+    let pos = txn.savepoints.iter().position(|s| s == name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("unknown savepoint: {:?}", name))
+    })?;
+    txn.savepoints.truncate(pos + 1);
+    Ok(format!("ROLLBACK TO SAVEPOINT {}", name))
+}
+
+/// Release a named savepoint and any savepoints nested inside it, and return the SQL
+/// to do so.
+#[pyfunction]
+pub fn release_savepoint(txn: &mut Transaction, name: &str) -> PyResult<String> {
+    ensure_active(txn)?;
+    let pos = txn.savepoints.iter().position(|s| s == name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("unknown savepoint: {:?}", name))
+    })?;
+    txn.savepoints.truncate(pos);
+    Ok(format!("RELEASE SAVEPOINT {}", name))
+}
+
+/// Commit `txn`, returning the commit SQL and the connection to return to the pool. In
+/// optimistic mode, pass `conflict=true` to raise `TxnConflictError` instead of committing.
+#[pyfunction]
+pub fn commit(py: Python<'_>, txn: &mut Transaction, conflict: bool) -> PyResult<(String, Py<PyAny>)> {
+    ensure_active(txn)?;
+    txn.active = false;
+    if txn.optimistic && conflict {
+        return Err(TxnConflictError::new_err(
+            "optimistic transaction conflict detected at commit",
+        ));
+    }
+    Ok(("COMMIT".to_string(), txn.conn.clone_ref(py)))
+}
+
+/// Roll back `txn` entirely, returning the rollback SQL and the connection to return
+/// to the pool.
+#[pyfunction]
+pub fn rollback(py: Python<'_>, txn: &mut Transaction) -> PyResult<(String, Py<PyAny>)> {
+    ensure_active(txn)?;
+    txn.active = false;
+    Ok(("ROLLBACK".to_string(), txn.conn.clone_ref(py)))
+}
+
 /// Statement cache
 static STATEMENT_CACHE: Lazy<Arc<Mutex<HashMap<String, Arc<Py<PyAny>>>>>> =
     Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
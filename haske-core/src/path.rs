@@ -1,24 +1,62 @@
 use pyo3::prelude::*;
+use pyo3::types::{PyInt, PyString};
 use regex::Regex;
+use std::collections::HashMap;
 
-/// Compile a Haske path into a regex string with named captures.
-/// Example: "/user/:id" -> r"^/user/(?P<id>[^/]+)$"
+/// The type constraint declared on a path parameter (`:name<type>`).
+enum ParamType {
+    Str,
+    Int,
+    Uuid,
+}
+
+impl ParamType {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "str" => Ok(ParamType::Str),
+            "int" => Ok(ParamType::Int),
+            "uuid" => Ok(ParamType::Uuid),
+            other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown param type: {}",
+                other
+            ))),
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            ParamType::Str => "[^/]+",
+            ParamType::Int => "[0-9]+",
+            ParamType::Uuid => {
+                "[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}"
+            }
+        }
+    }
+}
+
+/// Compile a Haske path into a regex string with named captures, plus the declared type
+/// of each parameter for `match_path` to coerce against.
+/// Examples:
+///   "/user/:id" -> (r"^/user/(?P<id>[^/]+)$", [("id", "str")])
+///   "/user/:id<int>" -> (r"^/user/(?P<id>[0-9]+)$", [("id", "int")])
+///   "/files/*rest" -> (r"^/files/(?P<rest>.+)$", [("rest", "str")])
 #[pyfunction]
-pub fn compile_path(path: &str) -> PyResult<String> {
+pub fn compile_path(path: &str) -> PyResult<(String, Vec<(String, String)>)> {
     if !path.starts_with('/') {
         return Err(pyo3::exceptions::PyValueError::new_err("path must start with /"));
     }
-    
+
     // Validate path doesn't contain invalid patterns
     if path.contains("::") || path.contains("//") {
         return Err(pyo3::exceptions::PyValueError::new_err("path contains invalid pattern"));
     }
-    
+
     let mut out = String::new();
     out.push('^');
     let mut chars = path.chars().peekable();
     let mut param_count = 0;
-    
+    let mut params: Vec<(String, String)> = Vec::new();
+
     while let Some(c) = chars.next() {
         if c == ':' {
             let mut name = String::new();
@@ -33,14 +71,57 @@ pub fn compile_path(path: &str) -> PyResult<String> {
             if name.is_empty() {
                 return Err(pyo3::exceptions::PyValueError::new_err("empty param name"));
             }
+
+            let mut type_name = "str".to_string();
+            if chars.peek() == Some(&'<') {
+                chars.next();
+                let mut constraint = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch == '>' {
+                        break;
+                    }
+                    constraint.push(ch);
+                    chars.next();
+                }
+                if chars.peek() != Some(&'>') {
+                    return Err(pyo3::exceptions::PyValueError::new_err(
+                        "unterminated type constraint",
+                    ));
+                }
+                chars.next();
+                type_name = constraint;
+            }
+            let param_type = ParamType::from_name(&type_name)?;
+
+            param_count += 1;
+            if param_count > 20 {
+                return Err(pyo3::exceptions::PyValueError::new_err("too many parameters in path (max 20)"));
+            }
+            out.push_str(&format!("(?P<{}>{})", name, param_type.pattern()));
+            params.push((name, type_name));
+        } else if c == '*' {
+            let mut name = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_alphanumeric() || ch == '_' {
+                    name.push(ch);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if name.is_empty() {
+                return Err(pyo3::exceptions::PyValueError::new_err("empty wildcard name"));
+            }
+
             param_count += 1;
             if param_count > 20 {
                 return Err(pyo3::exceptions::PyValueError::new_err("too many parameters in path (max 20)"));
             }
-            out.push_str(&format!("(?P<{}>[^/]+)", name));
+            out.push_str(&format!("(?P<{}>.+)", name));
+            params.push((name, "str".to_string()));
         } else {
             match c {
-                '.'|'+'|'*'|'?'|'(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' | '\\' => {
+                '.'|'+'|'?'|'(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' | '\\' => {
                     out.push('\\'); out.push(c);
                 }
                 other => out.push(other),
@@ -48,29 +129,52 @@ pub fn compile_path(path: &str) -> PyResult<String> {
         }
     }
     out.push('$');
-    
+
     // Validate the regex compiles correctly
     Regex::new(&out)
         .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("invalid regex generated: {}", e)))?;
-    
-    Ok(out)
+
+    Ok((out, params))
 }
 
-/// Validate if a path matches a pattern and extract parameters
+/// Validate if a path matches a pattern and extract parameters, optionally coercing each
+/// captured value to its declared type (as returned by `compile_path`) so Python handlers
+/// receive e.g. an `int` instead of a `str`.
 #[pyfunction]
-pub fn match_path(pattern: &str, path: &str) -> PyResult<Option<Vec<(String, String)>>> {
+#[pyo3(signature = (pattern, path, types=None))]
+pub fn match_path(
+    py: Python<'_>,
+    pattern: &str,
+    path: &str,
+    types: Option<Vec<(String, String)>>,
+) -> PyResult<Option<Vec<(String, Py<PyAny>)>>> {
     let regex = Regex::new(pattern)
         .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("invalid regex pattern: {}", e)))?;
-    
+
+    let type_map: HashMap<String, String> = types.unwrap_or_default().into_iter().collect();
+
     if let Some(caps) = regex.captures(path) {
         let mut params = Vec::new();
         for name in regex.capture_names().flatten() {
             if let Some(m) = caps.name(name) {
-                params.push((name.to_string(), m.as_str().to_string()));
+                let value = m.as_str();
+                let py_value: Py<PyAny> = match type_map.get(name).map(String::as_str) {
+                    Some("int") => {
+                        let i: i64 = value.parse().map_err(|_| {
+                            pyo3::exceptions::PyValueError::new_err(format!(
+                                "param {} is not a valid int",
+                                name
+                            ))
+                        })?;
+                        PyInt::new(py, i).unbind().into()
+                    }
+                    _ => PyString::new(py, value).unbind().into(),
+                };
+                params.push((name.to_string(), py_value));
             }
         }
         Ok(Some(params))
     } else {
         Ok(None)
     }
-}
\ No newline at end of file
+}
@@ -4,9 +4,14 @@ use pyo3::types::PyDict;
 use bytes::BytesMut;
 use base64::{engine::general_purpose, Engine as _};
 use sha1::{Sha1, Digest};
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
+/// The empty-DEFLATE-block trailer permessage-deflate strips from compressed payloads
+/// and re-appends before inflating, per RFC 7692.
+const DEFLATE_TAIL: [u8; 4] = [0x00, 0x00, 0xFF, 0xFF];
+
 /// WebSocket frame parser and builder
 #[pyclass]
 pub struct WebSocketFrame {
@@ -18,13 +23,29 @@ pub struct WebSocketFrame {
     pub is_final: bool,
     #[pyo3(get)]
     pub is_masked: bool,
+    /// Negotiated-extension bit (set by permessage-deflate on the first frame of a message).
+    #[pyo3(get)]
+    pub rsv1: bool,
+    #[pyo3(get)]
+    pub rsv2: bool,
+    #[pyo3(get)]
+    pub rsv3: bool,
 }
 
 #[pymethods]
 impl WebSocketFrame {
     #[new]
-    pub fn new(opcode: u8, payload: Vec<u8>, is_final: bool, is_masked: bool) -> Self {
-        Self { opcode, payload, is_final, is_masked }
+    #[pyo3(signature = (opcode, payload, is_final, is_masked, rsv1=false, rsv2=false, rsv3=false))]
+    pub fn new(
+        opcode: u8,
+        payload: Vec<u8>,
+        is_final: bool,
+        is_masked: bool,
+        rsv1: bool,
+        rsv2: bool,
+        rsv3: bool,
+    ) -> Self {
+        Self { opcode, payload, is_final, is_masked, rsv1, rsv2, rsv3 }
     }
 
     /// Parse a WebSocket frame from bytes
@@ -38,6 +59,9 @@ impl WebSocketFrame {
         let second_byte = frame_data[1];
         
         let is_final = (first_byte & 0x80) != 0;
+        let rsv1 = (first_byte & 0x40) != 0;
+        let rsv2 = (first_byte & 0x20) != 0;
+        let rsv3 = (first_byte & 0x10) != 0;
         let opcode = first_byte & 0x0F;
         let is_masked = (second_byte & 0x80) != 0;
         let mut payload_len = (second_byte & 0x7F) as usize;
@@ -100,13 +124,20 @@ impl WebSocketFrame {
             payload,
             is_final,
             is_masked,
+            rsv1,
+            rsv2,
+            rsv3,
         })
     }
 
     /// Convert to bytes for sending
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut frame = BytesMut::new();
-        let first_byte = if self.is_final { 0x80 } else { 0x00 } | (self.opcode & 0x0F);
+        let first_byte = (if self.is_final { 0x80 } else { 0x00 })
+            | (if self.rsv1 { 0x40 } else { 0x00 })
+            | (if self.rsv2 { 0x20 } else { 0x00 })
+            | (if self.rsv3 { 0x10 } else { 0x00 })
+            | (self.opcode & 0x0F);
         frame.extend_from_slice(&[first_byte]);
         
         if self.payload.len() <= 125 {
@@ -141,6 +172,9 @@ impl WebSocketFrame {
             payload: text.as_bytes().to_vec(),
             is_final: true,
             is_masked: false,
+            rsv1: false,
+            rsv2: false,
+            rsv3: false,
         }
     }
 
@@ -152,6 +186,9 @@ impl WebSocketFrame {
             payload: data.to_vec(),
             is_final: true,
             is_masked: false,
+            rsv1: false,
+            rsv2: false,
+            rsv3: false,
         }
     }
 
@@ -171,6 +208,9 @@ impl WebSocketFrame {
             payload,
             is_final: true,
             is_masked: false,
+            rsv1: false,
+            rsv2: false,
+            rsv3: false,
         }
     }
 
@@ -182,6 +222,9 @@ impl WebSocketFrame {
             payload: data.unwrap_or_default().to_vec(),
             is_final: true,
             is_masked: false,
+            rsv1: false,
+            rsv2: false,
+            rsv3: false,
         }
     }
 
@@ -193,10 +236,194 @@ impl WebSocketFrame {
             payload: data.unwrap_or_default().to_vec(),
             is_final: true,
             is_masked: false,
+            rsv1: false,
+            rsv2: false,
+            rsv3: false,
         }
     }
 }
 
+/// permessage-deflate (RFC 7692) compression extension. Decompresses a message whose
+/// first frame has RSV1 set, and compresses outgoing payloads for frames that should
+/// carry RSV1.
+#[pyclass]
+pub struct PerMessageDeflate {
+    no_context_takeover: bool,
+    compressor: Compress,
+    decompressor: Decompress,
+}
+
+#[pymethods]
+impl PerMessageDeflate {
+    #[new]
+    #[pyo3(signature = (no_context_takeover=false))]
+    pub fn new(no_context_takeover: bool) -> Self {
+        Self {
+            no_context_takeover,
+            compressor: Compress::new(Compression::default(), false),
+            decompressor: Decompress::new(false),
+        }
+    }
+
+    /// Inflate a message payload whose first frame had RSV1 set. The trailing empty
+    /// DEFLATE block stripped during compression is re-appended before inflating.
+    pub fn decompress(&mut self, payload: &[u8]) -> PyResult<Vec<u8>> {
+        if self.no_context_takeover {
+            self.decompressor = Decompress::new(false);
+        }
+        let mut input = payload.to_vec();
+        input.extend_from_slice(&DEFLATE_TAIL);
+        run_decompress(&mut self.decompressor, &input, FlushDecompress::Sync)
+    }
+
+    /// Deflate a message payload with a sync flush and strip the trailing empty DEFLATE
+    /// block, ready to be sent as the first frame of a message with RSV1 set.
+    pub fn compress(&mut self, payload: &[u8]) -> PyResult<Vec<u8>> {
+        if self.no_context_takeover {
+            self.compressor = Compress::new(Compression::default(), false);
+        }
+        let mut output = run_compress(&mut self.compressor, payload, FlushCompress::Sync)?;
+        if output.ends_with(&DEFLATE_TAIL) {
+            output.truncate(output.len() - DEFLATE_TAIL.len());
+        }
+        Ok(output)
+    }
+}
+
+/// Run `compress_vec` to completion, growing the output buffer as needed instead of
+/// capping it at a fixed guess — a single call only fills whatever spare capacity the
+/// `Vec` already has, so incompressible payloads that expand past that guess would
+/// otherwise be silently truncated.
+fn run_compress(compress: &mut Compress, mut input: &[u8], flush: FlushCompress) -> PyResult<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len().max(64));
+    loop {
+        output.reserve(8192);
+        let capacity = output.capacity();
+        let before_in = compress.total_in();
+        let status = compress.compress_vec(input, &mut output, flush).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "permessage-deflate compress error: {}",
+                e
+            ))
+        })?;
+        input = &input[(compress.total_in() - before_in) as usize..];
+        let filled_buffer = output.len() == capacity;
+
+        // `Status::Ok` with no input left does NOT mean there's no more output pending: if
+        // the call also filled the buffer it was given, flate2 may still be holding buffered
+        // output internally and must be called again (with an empty input slice) to drain it.
+        // Don't break on `filled_buffer` alone either — under `FlushCompress::Sync` a
+        // compressor with no real data left can keep re-emitting a few sync-marker bytes
+        // forever, so termination must still require `StreamEnd` or exhausted input.
+        if status == Status::StreamEnd || (input.is_empty() && !filled_buffer) {
+            break;
+        }
+    }
+    Ok(output)
+}
+
+/// Run `decompress_vec` to completion, growing the output buffer as needed instead of
+/// capping it at a fixed guess — a single call only fills whatever spare capacity the
+/// `Vec` already has, so highly-compressible messages that inflate past that guess would
+/// otherwise be silently truncated.
+fn run_decompress(
+    decompress: &mut Decompress,
+    mut input: &[u8],
+    flush: FlushDecompress,
+) -> PyResult<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len().max(64) * 4);
+    loop {
+        output.reserve(8192);
+        let capacity = output.capacity();
+        let before_in = decompress.total_in();
+        let status = decompress.decompress_vec(input, &mut output, flush).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "permessage-deflate decompress error: {}",
+                e
+            ))
+        })?;
+        input = &input[(decompress.total_in() - before_in) as usize..];
+        let filled_buffer = output.len() == capacity;
+
+        // As with `run_compress`: a call that both exhausts the input and fills the output
+        // buffer to capacity may still be holding buffered output internally, so input
+        // exhaustion alone is not a safe stopping point — only stop once a call returns
+        // without filling the buffer it was given (or the stream is explicitly done).
+        if status == Status::StreamEnd || (input.is_empty() && !filled_buffer) {
+            break;
+        }
+    }
+    Ok(output)
+}
+
+/// Reassembles a logical message split across multiple frames (FIN=0 plus opcode-0
+/// continuations) back into a single `(opcode, payload)` pair.
+#[pyclass]
+pub struct MessageAssembler {
+    in_progress_opcode: Option<u8>,
+    buffer: Vec<u8>,
+}
+
+#[pymethods]
+impl MessageAssembler {
+    #[new]
+    pub fn new() -> Self {
+        Self {
+            in_progress_opcode: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Feed a parsed frame into the assembler. Returns `Some((opcode, payload))` once a
+    /// complete message has been reassembled, or `None` while more fragments are expected.
+    /// Control frames (opcode >= 8) pass straight through and never disturb an
+    /// in-progress data message.
+    pub fn feed(&mut self, frame: &WebSocketFrame) -> PyResult<Option<(u8, Vec<u8>)>> {
+        if frame.opcode >= 8 {
+            return Ok(Some((frame.opcode, frame.payload.clone())));
+        }
+
+        if frame.opcode == 0 {
+            let opcode = self.in_progress_opcode.ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(
+                    "continuation frame received with no message in progress",
+                )
+            })?;
+            self.buffer.extend_from_slice(&frame.payload);
+            if frame.is_final {
+                self.in_progress_opcode = None;
+                Ok(Some((opcode, std::mem::take(&mut self.buffer))))
+            } else {
+                Ok(None)
+            }
+        } else {
+            if self.in_progress_opcode.is_some() {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "new data frame arrived while a message is still open",
+                ));
+            }
+            if frame.is_final {
+                Ok(Some((frame.opcode, frame.payload.clone())))
+            } else {
+                self.in_progress_opcode = Some(frame.opcode);
+                self.buffer = frame.payload.clone();
+                Ok(None)
+            }
+        }
+    }
+
+    /// True if a fragmented message is currently being assembled.
+    pub fn in_progress(&self) -> bool {
+        self.in_progress_opcode.is_some()
+    }
+
+    /// Discard any partially assembled message and reset to a clean state.
+    pub fn reset(&mut self) {
+        self.in_progress_opcode = None;
+        self.buffer.clear();
+    }
+}
+
 /// WebSocket connection manager for broadcasting
 #[pyclass]
 pub struct WebSocketManager {
@@ -361,6 +588,82 @@ pub fn validate_websocket_frame(frame_data: &[u8]) -> bool {
     frame_data.len() >= offset + payload_len
 }
 
+/// Raised when a frame or close code fails RFC 6455 server-side conformance checks.
+pyo3::create_exception!(_haske_core, WsError, pyo3::exceptions::PyException);
+
+/// Enforce RFC 6455 conformance rules a server must apply to frames from a client.
+/// Rejects unmasked client frames, RSV bits set without a negotiated extension,
+/// oversized or fragmented control frames, and unknown opcodes.
+#[pyfunction]
+#[pyo3(signature = (frame, extension_negotiated=false, validate_utf8=false))]
+pub fn validate_server_frame(
+    frame: &WebSocketFrame,
+    extension_negotiated: bool,
+    validate_utf8: bool,
+) -> PyResult<()> {
+    if !frame.is_masked {
+        return Err(WsError::new_err("client frames must be masked"));
+    }
+
+    if !extension_negotiated && (frame.rsv1 || frame.rsv2 || frame.rsv3) {
+        return Err(WsError::new_err(
+            "RSV bits set but no extension has been negotiated",
+        ));
+    }
+
+    if frame.opcode >= 8 {
+        if frame.payload.len() > 125 {
+            return Err(WsError::new_err("control frame payload exceeds 125 bytes"));
+        }
+        if !frame.is_final {
+            return Err(WsError::new_err("control frames must not be fragmented"));
+        }
+        if matches!(frame.opcode, 11..=15) {
+            return Err(WsError::new_err(format!("unknown opcode: {}", frame.opcode)));
+        }
+    } else if matches!(frame.opcode, 3..=7) {
+        return Err(WsError::new_err(format!("unknown opcode: {}", frame.opcode)));
+    }
+
+    if validate_utf8 && frame.opcode == 1 && std::str::from_utf8(&frame.payload).is_err() {
+        return Err(WsError::new_err("text frame payload is not valid UTF-8"));
+    }
+
+    Ok(())
+}
+
+/// Parses and validates a 2-byte WebSocket close-frame status code against the
+/// RFC 6455 defined ranges.
+#[pyclass]
+pub struct CloseCode {
+    #[pyo3(get)]
+    pub code: u16,
+}
+
+#[pymethods]
+impl CloseCode {
+    /// Parse the 2-byte close code from a close-frame payload, rejecting reserved
+    /// codes (1004-1006, 1015) and unassigned codes in the 1000-2999 range.
+    #[staticmethod]
+    pub fn parse(payload: &[u8]) -> PyResult<Self> {
+        if payload.len() < 2 {
+            return Err(WsError::new_err("close payload must be at least 2 bytes"));
+        }
+        let code = u16::from_be_bytes([payload[0], payload[1]]);
+        if !Self::is_valid(code) {
+            return Err(WsError::new_err(format!("invalid close code: {}", code)));
+        }
+        Ok(Self { code })
+    }
+
+    /// True if `code` is one of the defined status codes (1000-1003, 1007-1011) or
+    /// falls in the application-defined range (3000-4999).
+    #[staticmethod]
+    pub fn is_valid(code: u16) -> bool {
+        matches!(code, 1000..=1003 | 1007..=1011 | 3000..=4999)
+    }
+}
+
 /// Fast frame type detection
 #[pyfunction]
 pub fn get_frame_type(frame_data: &[u8]) -> Option<u8> {
@@ -414,4 +717,70 @@ pub fn get_payload_length(frame_data: &[u8]) -> PyResult<usize> {
     }
     
     Ok(payload_len)
+}
+
+/// Push-based incremental WebSocket frame decoder for sockets that deliver arbitrary-sized
+/// chunks. Bytes are fed in via `feed` and complete frames are pulled out one at a time via
+/// `next_frame`, which leaves any partial trailing frame buffered for the next call.
+#[pyclass]
+pub struct FrameDecoder {
+    buffer: BytesMut,
+}
+
+#[pymethods]
+impl FrameDecoder {
+    #[new]
+    pub fn new() -> Self {
+        Self { buffer: BytesMut::new() }
+    }
+
+    /// Append newly-received bytes to the internal buffer.
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Parse and remove exactly one complete frame from the front of the buffer.
+    /// Returns `None` if the buffer doesn't yet hold a full frame.
+    pub fn next_frame(&mut self) -> PyResult<Option<WebSocketFrame>> {
+        let buf = &self.buffer[..];
+        if buf.len() < 2 {
+            return Ok(None);
+        }
+
+        let second_byte = buf[1];
+        let is_masked = (second_byte & 0x80) != 0;
+        let base_len = (second_byte & 0x7F) as usize;
+
+        let (mut offset, payload_len) = if base_len == 126 {
+            if buf.len() < 4 {
+                return Ok(None);
+            }
+            (4, ((buf[2] as usize) << 8) | (buf[3] as usize))
+        } else if base_len == 127 {
+            if buf.len() < 10 {
+                return Ok(None);
+            }
+            (
+                10,
+                ((buf[6] as usize) << 24)
+                    | ((buf[7] as usize) << 16)
+                    | ((buf[8] as usize) << 8)
+                    | (buf[9] as usize),
+            )
+        } else {
+            (2, base_len)
+        };
+
+        if is_masked {
+            offset += 4;
+        }
+
+        let total_len = offset + payload_len;
+        if buf.len() < total_len {
+            return Ok(None);
+        }
+
+        let frame_bytes = self.buffer.split_to(total_len);
+        WebSocketFrame::parse(&frame_bytes).map(Some)
+    }
 }
\ No newline at end of file